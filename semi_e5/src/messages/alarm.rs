@@ -0,0 +1,330 @@
+// Copyright © 2024 Nathaniel Hardesty
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+//! # ALARM MANAGEMENT
+//!
+//! ---------------------------------------------------------------------------
+//!
+//! Maintains the equipment's active-alarm list from a live stream of
+//! [Stream 5] messages.
+//!
+//! [AlarmReportSend] (S5F1) sets or clears an entry keyed by [AlarmID],
+//! [EnableDisableAlarmSend]/[EnableDisableAllAlarmSend] (S5F3) gate which
+//! alarms are reported at all, and the resulting state can be queried
+//! directly to synthesize [ListAlarmsData] (S5F6) and
+//! [ListEnabledAlarmsData] (S5F8) responses.
+//!
+//! ---------------------------------------------------------------------------
+//!
+//! [Stream 5]: crate::messages::s5
+
+use std::collections::{HashMap, HashSet};
+
+use crate::items::*;
+use super::s5::*;
+
+/// The category carried in bits 1-7 of an [AlarmCode], as categorized in
+/// the [Stream 5] module header.
+///
+/// [Stream 5]: crate::messages::s5
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmCategory {
+    /// Condition may be dangerous to people.
+    PersonalSafety,
+    /// Condition may harm equipment.
+    EquipmentSafety,
+    /// Parameter variation outside of preset limits - may harm product.
+    ParameterControlWarning,
+    /// Parameter variation outside of reasonable control limits - may
+    /// indicate an equipment malfunction.
+    ParameterControlError,
+    /// Intervention required before normal use of equipment can resume.
+    IrrecoverableError,
+    /// An unexpected condition has occurred, but operation can continue.
+    EquipmentStatusWarning,
+    /// A signal from a process program indicating that a particular step
+    /// has been reached.
+    AttentionFlags,
+    /// A condition which may cause loss of data; usually related to
+    /// [Stream 6](crate::messages::s6).
+    DataIntegrity,
+}
+
+impl AlarmCategory {
+    /// The four-level priority this category maps to, for filtering and
+    /// prioritizing incoming [AlarmReportSend] messages.
+    pub fn severity(&self) -> AlarmSeverity {
+        match self {
+            AlarmCategory::PersonalSafety => AlarmSeverity::Critical,
+            AlarmCategory::EquipmentSafety => AlarmSeverity::Critical,
+            AlarmCategory::IrrecoverableError => AlarmSeverity::Major,
+            AlarmCategory::ParameterControlError => AlarmSeverity::Major,
+            AlarmCategory::ParameterControlWarning => AlarmSeverity::Minor,
+            AlarmCategory::DataIntegrity => AlarmSeverity::Minor,
+            AlarmCategory::EquipmentStatusWarning => AlarmSeverity::Warning,
+            AlarmCategory::AttentionFlags => AlarmSeverity::Warning,
+        }
+    }
+}
+
+/// A four-level priority ordering derived from [AlarmCategory], so
+/// downstream filtering and prioritization does not need to re-implement
+/// the bit arithmetic of [AlarmCode] nor enumerate every category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlarmSeverity {
+    Warning,
+    Minor,
+    Major,
+    Critical,
+}
+
+impl AlarmCode {
+    /// Decodes the alarm category carried in bits 1-7, or `None` if the
+    /// value does not match a category defined by [Stream 5].
+    ///
+    /// This is a read of the raw byte, not a conversion, so it is lossless
+    /// and the [AlarmCode] remains round-trippable to its original value.
+    ///
+    /// [Stream 5]: crate::messages::s5
+    pub fn category(&self) -> Option<AlarmCategory> {
+        match self.0 & 0x7F {
+            1 => Some(AlarmCategory::PersonalSafety),
+            2 => Some(AlarmCategory::EquipmentSafety),
+            3 => Some(AlarmCategory::ParameterControlWarning),
+            4 => Some(AlarmCategory::ParameterControlError),
+            5 => Some(AlarmCategory::IrrecoverableError),
+            6 => Some(AlarmCategory::EquipmentStatusWarning),
+            7 => Some(AlarmCategory::AttentionFlags),
+            8 => Some(AlarmCategory::DataIntegrity),
+            _ => None,
+        }
+    }
+
+    /// The severity of [AlarmCode::category], or `None` if the category is
+    /// unrecognized.
+    pub fn severity(&self) -> Option<AlarmSeverity> {
+        self.category().map(|category| category.severity())
+    }
+
+    /// `true` if bit 8 (0x80) marks this as an alarm *set* event.
+    pub fn is_set(&self) -> bool {
+        self.0 & 0x80 != 0
+    }
+
+    /// `true` if bit 8 (0x80) marks this as an alarm *clear* event.
+    pub fn is_cleared(&self) -> bool {
+        !self.is_set()
+    }
+}
+
+/// A single currently-active alarm, as last reported by [AlarmReportSend].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveAlarm {
+    pub code: AlarmCode,
+    pub id: AlarmID,
+    pub text: AlarmText,
+}
+
+/// The transition applied to the active-alarm set by an incoming
+/// [AlarmReportSend].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmTransition {
+    /// The alarm became active.
+    Set,
+    /// The alarm was cleared.
+    Cleared,
+}
+
+/// Tracks the equipment's active-alarm list and enabled/disabled state from
+/// a stream of [AlarmReportSend] (S5F1), [EnableDisableAlarmSend], and
+/// [EnableDisableAllAlarmSend] (S5F3) messages.
+///
+/// Queries against the live state can be used to synthesize
+/// [ListAlarmsData] (S5F6) and [ListEnabledAlarmsData] (S5F8) responses
+/// without the host having to hand-build them from raw reports.
+#[derive(Default)]
+pub struct AlarmManager {
+    active: HashMap<AlarmID, ActiveAlarm>,
+    disabled_all: bool,
+    disabled: HashSet<AlarmID>,
+    listeners: Vec<Box<dyn FnMut(&ActiveAlarm, AlarmTransition)>>,
+}
+
+impl AlarmManager {
+    /// Creates an empty registry with no active alarms and everything
+    /// enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a callback invoked with the affected alarm and the
+    /// transition applied, each time [AlarmManager::handle_report] changes
+    /// the active-alarm set.
+    pub fn on_transition(&mut self, listener: impl FnMut(&ActiveAlarm, AlarmTransition) + 'static) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Applies an incoming [AlarmReportSend], inserting or removing the
+    /// alarm from the active set based on the set/clear bit of its
+    /// [AlarmCode].
+    ///
+    /// Returns `None` if the alarm is currently disabled, in which case the
+    /// report is suppressed and the active set is left unchanged.
+    pub fn handle_report(&mut self, report: &AlarmReportSend) -> Option<AlarmTransition> {
+        let (code, id, text) = &report.0;
+        if self.is_disabled(id) {
+            return None;
+        }
+        let alarm = ActiveAlarm { code: code.clone(), id: id.clone(), text: text.clone() };
+        let (transition, changed) = if code.is_set() {
+            let previous = self.active.insert(id.clone(), alarm.clone());
+            (AlarmTransition::Set, previous.as_ref() != Some(&alarm))
+        } else {
+            (AlarmTransition::Cleared, self.active.remove(id).is_some())
+        };
+        if changed {
+            for listener in &mut self.listeners {
+                listener(&alarm, transition);
+            }
+        }
+        Some(transition)
+    }
+
+    /// Applies an [EnableDisableAlarmSend], enabling or disabling reports
+    /// for a single alarm.
+    ///
+    /// Disabling an alarm does not clear it from the active set if it is
+    /// already set; it only suppresses future reports and excludes it from
+    /// [AlarmManager::list_enabled_alarms].
+    ///
+    /// While every alarm is disabled by
+    /// [AlarmManager::handle_enable_disable_all], enabling a single alarm
+    /// has no effect; only an enable-all re-enables it.
+    pub fn handle_enable_disable(&mut self, request: &EnableDisableAlarmSend) {
+        let (enable, id) = &request.0;
+        match enable {
+            AlarmEnableDisable::Enable => {
+                self.disabled.remove(id);
+            }
+            AlarmEnableDisable::Disable => {
+                self.disabled.insert(id.clone());
+            }
+        }
+    }
+
+    /// Applies an [EnableDisableAllAlarmSend], enabling or disabling every
+    /// alarm at once and clearing any individual overrides.
+    pub fn handle_enable_disable_all(&mut self, request: &EnableDisableAllAlarmSend) {
+        let (enable, _) = &request.0;
+        self.disabled.clear();
+        self.disabled_all = matches!(enable, AlarmEnableDisable::Disable);
+    }
+
+    fn is_disabled(&self, id: &AlarmID) -> bool {
+        self.disabled_all || self.disabled.contains(id)
+    }
+
+    /// Returns `true` if the given alarm is currently active.
+    pub fn is_active(&self, id: &AlarmID) -> bool {
+        self.active.contains_key(id)
+    }
+
+    /// Synthesizes a [ListAlarmsData] (S5F6) response from the current
+    /// active-alarm set.
+    pub fn list_alarms(&self) -> ListAlarmsData {
+        ListAlarmsData(
+            self.active
+                .values()
+                .map(|a| (a.code.clone(), a.id.clone(), a.text.clone()))
+                .collect(),
+        )
+    }
+
+    /// Synthesizes a [ListEnabledAlarmsData] (S5F8) response from the
+    /// current active-alarm set, excluding any alarm that is currently
+    /// disabled.
+    pub fn list_enabled_alarms(&self) -> ListEnabledAlarmsData {
+        ListEnabledAlarmsData(
+            self.active
+                .values()
+                .filter(|a| !self.is_disabled(&a.id))
+                .map(|a| (a.code.clone(), a.id.clone(), a.text.clone()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn category_decodes_bits_one_through_seven_and_ignores_the_set_clear_bit() {
+        assert_eq!(AlarmCode(0x01).category(), Some(AlarmCategory::PersonalSafety));
+        assert_eq!(AlarmCode(0x81).category(), Some(AlarmCategory::PersonalSafety));
+        assert_eq!(AlarmCode(0x08).category(), Some(AlarmCategory::DataIntegrity));
+        assert_eq!(AlarmCode(0x00).category(), None);
+    }
+
+    #[test]
+    fn severity_follows_category_and_is_lossless() {
+        assert_eq!(AlarmCode(0x01).severity(), Some(AlarmSeverity::Critical));
+        assert_eq!(AlarmCode(0x06).severity(), Some(AlarmSeverity::Warning));
+        assert_eq!(AlarmCode(0x00).severity(), None);
+
+        let code = AlarmCode(0x85);
+        let _ = code.category();
+        assert_eq!(code.0, 0x85);
+    }
+
+    #[test]
+    fn is_set_and_is_cleared_read_bit_eight() {
+        assert!(AlarmCode(0x81).is_set());
+        assert!(!AlarmCode(0x81).is_cleared());
+        assert!(AlarmCode(0x01).is_cleared());
+        assert!(!AlarmCode(0x01).is_set());
+    }
+
+    fn report(alcd: u8, alid: u32) -> AlarmReportSend {
+        AlarmReportSend((AlarmCode(alcd), AlarmID(alid), AlarmText("alarm text".to_string())))
+    }
+
+    #[test]
+    fn set_then_clear_round_trips_through_active_set() {
+        let mut manager = AlarmManager::new();
+        assert_eq!(manager.handle_report(&report(0x81, 1)), Some(AlarmTransition::Set));
+        assert!(manager.is_active(&AlarmID(1)));
+
+        assert_eq!(manager.handle_report(&report(0x01, 1)), Some(AlarmTransition::Cleared));
+        assert!(!manager.is_active(&AlarmID(1)));
+    }
+
+    #[test]
+    fn disabled_alarm_is_suppressed_and_active_set_unchanged() {
+        let mut manager = AlarmManager::new();
+        manager.handle_enable_disable(&EnableDisableAlarmSend((AlarmEnableDisable::Disable, AlarmID(1))));
+
+        assert_eq!(manager.handle_report(&report(0x81, 1)), None);
+        assert!(!manager.is_active(&AlarmID(1)));
+
+        manager.handle_enable_disable(&EnableDisableAlarmSend((AlarmEnableDisable::Enable, AlarmID(1))));
+        assert_eq!(manager.handle_report(&report(0x81, 1)), Some(AlarmTransition::Set));
+    }
+}
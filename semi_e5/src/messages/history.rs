@@ -0,0 +1,211 @@
+// Copyright © 2024 Nathaniel Hardesty
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+//! # EVENT & ALARM HISTORY
+//!
+//! ---------------------------------------------------------------------------
+//!
+//! Append-only, time-stamped log of every alarm transition ([AlarmReportSend],
+//! S5F1) and event report ([EventReport]/[EventReportData], S6F11/S6F16).
+//!
+//! Where [AlarmManager] only exposes the alarms that are active right now,
+//! every record appended here is kept, so a query can answer what alarms
+//! fired and what variables an event carried over a past window instead of
+//! only what is true at this instant.
+//!
+//! ---------------------------------------------------------------------------
+//!
+//! [AlarmReportSend]: super::s5::AlarmReportSend
+//! [EventReport]: super::s6::EventReport
+//! [EventReportData]: super::s6::EventReportData
+//! [AlarmManager]: super::alarm::AlarmManager
+
+use std::time::SystemTime;
+
+use crate::items::*;
+use super::alarm::{AlarmCategory, AlarmSeverity, AlarmTransition};
+use super::report::ResolvedReport;
+
+/// Which message produced a [HistoryRecord].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistorySource {
+    /// [AlarmReportSend](super::s5::AlarmReportSend) (S5F1).
+    S5F1,
+    /// [EventReport](super::s6::EventReport) (S6F11).
+    S6F11,
+    /// [EventReportData](super::s6::EventReportData) (S6F16).
+    S6F16,
+}
+
+/// The subject of a [HistoryRecord]: either an alarm transition or an
+/// event report.
+#[derive(Debug, Clone)]
+pub enum HistoryKind {
+    /// An [AlarmReportSend](super::s5::AlarmReportSend) (S5F1) transition.
+    Alarm { id: AlarmID, text: AlarmText, transition: AlarmTransition },
+    /// An [EventReport](super::s6::EventReport)/[EventReportData](super::s6::EventReportData)
+    /// (S6F11/S6F16) report, resolved against a
+    /// [ReportRegistry](super::report::ReportRegistry) into named
+    /// variables.
+    Event { ceid: CollectionEventID, reports: Vec<(ReportID, ResolvedReport)> },
+}
+
+/// A single time-stamped entry in the [History] log.
+#[derive(Debug, Clone)]
+pub struct HistoryRecord {
+    pub timestamp: SystemTime,
+    pub source: HistorySource,
+    pub category: Option<AlarmCategory>,
+    pub severity: Option<AlarmSeverity>,
+    pub kind: HistoryKind,
+}
+
+/// Append-only log of every alarm transition and event report, queryable
+/// by time range, by [AlarmID]/[CollectionEventID], and by
+/// [AlarmSeverity].
+///
+/// Distinct from [AlarmManager], which tracks only the *current* active
+/// alarms; [History] retains every transition and report ever recorded so
+/// operators can reconstruct what happened without the host storing raw
+/// bytes itself.
+#[derive(Debug, Default)]
+pub struct History {
+    records: Vec<HistoryRecord>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an alarm transition, as produced by
+    /// [AlarmManager::handle_report](super::alarm::AlarmManager::handle_report).
+    pub fn record_alarm(
+        &mut self,
+        timestamp: SystemTime,
+        code: &AlarmCode,
+        id: AlarmID,
+        text: AlarmText,
+        transition: AlarmTransition,
+    ) {
+        self.records.push(HistoryRecord {
+            timestamp,
+            source: HistorySource::S5F1,
+            category: code.category(),
+            severity: code.severity(),
+            kind: HistoryKind::Alarm { id, text, transition },
+        });
+    }
+
+    /// Appends an event report, as resolved by
+    /// [ReportRegistry::resolve_event_report](super::report::ReportRegistry::resolve_event_report)
+    /// or
+    /// [ReportRegistry::resolve_event_report_data](super::report::ReportRegistry::resolve_event_report_data).
+    ///
+    /// `source` records whether the report arrived via S6F11 or S6F16.
+    pub fn record_event(
+        &mut self,
+        timestamp: SystemTime,
+        source: HistorySource,
+        ceid: CollectionEventID,
+        reports: Vec<(ReportID, ResolvedReport)>,
+    ) {
+        self.records.push(HistoryRecord {
+            timestamp,
+            source,
+            category: None,
+            severity: None,
+            kind: HistoryKind::Event { ceid, reports },
+        });
+    }
+
+    /// Iterates every record in the log, oldest first, suitable for
+    /// replaying history.
+    pub fn iter(&self) -> impl Iterator<Item = &HistoryRecord> {
+        self.records.iter()
+    }
+
+    /// Returns every record whose timestamp falls within `range`.
+    pub fn query_time_range(&self, range: std::ops::Range<SystemTime>) -> Vec<&HistoryRecord> {
+        self.records.iter().filter(|r| range.contains(&r.timestamp)).collect()
+    }
+
+    /// Returns every alarm record for the given [AlarmID].
+    pub fn query_alarm(&self, id: &AlarmID) -> Vec<&HistoryRecord> {
+        self.records
+            .iter()
+            .filter(|r| matches!(&r.kind, HistoryKind::Alarm { id: recorded, .. } if recorded == id))
+            .collect()
+    }
+
+    /// Returns every event record for the given [CollectionEventID].
+    pub fn query_event(&self, ceid: &CollectionEventID) -> Vec<&HistoryRecord> {
+        self.records
+            .iter()
+            .filter(|r| matches!(&r.kind, HistoryKind::Event { ceid: recorded, .. } if recorded == ceid))
+            .collect()
+    }
+
+    /// Returns every record at or above the given [AlarmSeverity].
+    pub fn query_severity(&self, min: AlarmSeverity) -> Vec<&HistoryRecord> {
+        self.records.iter().filter(|r| r.severity.map_or(false, |s| s >= min)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_event_finds_record_by_ceid_and_keeps_resolved_reports() {
+        let mut history = History::new();
+        let reports: Vec<(ReportID, ResolvedReport)> = vec![(ReportID(1), Vec::new())];
+        history.record_event(SystemTime::UNIX_EPOCH, HistorySource::S6F11, CollectionEventID(100), reports.clone());
+
+        let found = history.query_event(&CollectionEventID(100));
+        assert_eq!(found.len(), 1);
+        match &found[0].kind {
+            HistoryKind::Event { ceid, reports: recorded } => {
+                assert_eq!(*ceid, CollectionEventID(100));
+                assert_eq!(*recorded, reports);
+            }
+            HistoryKind::Alarm { .. } => panic!("expected an event record"),
+        }
+        assert_eq!(found[0].source, HistorySource::S6F11);
+        assert!(history.query_event(&CollectionEventID(200)).is_empty());
+    }
+
+    #[test]
+    fn query_severity_excludes_event_records_which_carry_no_severity() {
+        let mut history = History::new();
+        history.record_event(SystemTime::UNIX_EPOCH, HistorySource::S6F16, CollectionEventID(1), Vec::new());
+        history.record_alarm(
+            SystemTime::UNIX_EPOCH,
+            &AlarmCode(0x81),
+            AlarmID(1),
+            AlarmText("alarm text".to_string()),
+            AlarmTransition::Set,
+        );
+
+        let found = history.query_severity(AlarmSeverity::Warning);
+        assert_eq!(found.len(), 1);
+        assert!(matches!(found[0].kind, HistoryKind::Alarm { .. }));
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright © 2024 Nathaniel Hardesty
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+//! # REPORT DEFINITIONS
+//!
+//! ---------------------------------------------------------------------------
+//!
+//! Resolves the positional report payloads carried by [EventReport] (S6F11)
+//! and [EventReportData] (S6F16) into named, typed records, using the
+//! RPTID -> VID definitions configured via Stream 2 (`DefineReport`).
+//!
+//! A raw `(ReportID, VecList<Item>)` pair gives no way to tell which
+//! variable each [Item] is; [ReportRegistry] turns it into a
+//! self-describing `Vec<(VariableID, Item)>`, which is what a host
+//! actually needs to log or act on.
+//!
+//! ---------------------------------------------------------------------------
+//!
+//! [EventReport]: super::s6::EventReport
+//! [EventReportData]: super::s6::EventReportData
+
+use std::collections::HashMap;
+
+use crate::items::*;
+use super::s6::*;
+
+/// The ordered list of [VariableID]s that make up a single report, as
+/// configured via Stream 2 (`DefineReport`).
+#[derive(Debug, Clone, Default)]
+pub struct ReportDefinition {
+    pub variables: Vec<VariableID>,
+}
+
+/// A [ReportID]'s [VariableID]s resolved against an incoming report's
+/// [Item] values, in report order.
+pub type ResolvedReport = Vec<(VariableID, Item)>;
+
+/// The arity `M` of an incoming report did not match the number of
+/// [VariableID]s in its registered [ReportDefinition].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportArityMismatch {
+    pub report_id: ReportID,
+    pub expected: usize,
+    pub actual: usize,
+}
+
+/// Registry of known [ReportDefinition]s, keyed by [ReportID], used to
+/// resolve the reports carried by [EventReport] and [EventReportData] into
+/// named, typed records.
+#[derive(Debug, Default)]
+pub struct ReportRegistry {
+    definitions: HashMap<ReportID, ReportDefinition>,
+}
+
+impl ReportRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers or replaces the definition for a [ReportID], as would be
+    /// configured via Stream 2.
+    pub fn define(&mut self, report_id: ReportID, definition: ReportDefinition) {
+        self.definitions.insert(report_id, definition);
+    }
+
+    /// Resolves a single `(ReportID, VecList<Item>)` pair against its
+    /// registered [ReportDefinition], zipping each [Item] with its
+    /// [VariableID].
+    ///
+    /// Returns `Ok(None)` if no definition is registered for the
+    /// [ReportID], since the payload cannot be named without one.
+    pub fn resolve_report(
+        &self,
+        report_id: &ReportID,
+        items: &VecList<Item>,
+    ) -> Result<Option<ResolvedReport>, ReportArityMismatch> {
+        let definition = match self.definitions.get(report_id) {
+            Some(definition) => definition,
+            None => return Ok(None),
+        };
+        if definition.variables.len() != items.len() {
+            return Err(ReportArityMismatch {
+                report_id: report_id.clone(),
+                expected: definition.variables.len(),
+                actual: items.len(),
+            });
+        }
+        Ok(Some(definition.variables.iter().cloned().zip(items.iter().cloned()).collect()))
+    }
+
+    /// Resolves every report carried by an [EventReport], grouped under its
+    /// [CollectionEventID].
+    ///
+    /// Reports with no registered definition are skipped rather than
+    /// failing the whole event, since most collection events only carry a
+    /// handful of known reports.
+    pub fn resolve_event_report(
+        &self,
+        report: &EventReport,
+    ) -> Result<(CollectionEventID, Vec<(ReportID, ResolvedReport)>), ReportArityMismatch> {
+        let (_, ceid, reports) = &report.0;
+        Ok((ceid.clone(), self.resolve_reports(reports)?))
+    }
+
+    /// Resolves every report carried by an [EventReportData], grouped under
+    /// its [CollectionEventID].
+    pub fn resolve_event_report_data(
+        &self,
+        report: &EventReportData,
+    ) -> Result<(CollectionEventID, Vec<(ReportID, ResolvedReport)>), ReportArityMismatch> {
+        let (_, ceid, reports) = &report.0;
+        Ok((ceid.clone(), self.resolve_reports(reports)?))
+    }
+
+    /// Shared by [ReportRegistry::resolve_event_report] and
+    /// [ReportRegistry::resolve_event_report_data]: resolves every report in
+    /// the list, keeping each [ReportID] paired with its resolved payload
+    /// so position in the list no longer matters once unregistered reports
+    /// are skipped.
+    fn resolve_reports(
+        &self,
+        reports: &VecList<(ReportID, VecList<Item>)>,
+    ) -> Result<Vec<(ReportID, ResolvedReport)>, ReportArityMismatch> {
+        let mut resolved = Vec::with_capacity(reports.len());
+        for (report_id, items) in reports.iter() {
+            if let Some(r) = self.resolve_report(report_id, items)? {
+                resolved.push((report_id.clone(), r));
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_report_without_definition_returns_none() {
+        let registry = ReportRegistry::new();
+        let items: VecList<Item> = std::iter::empty().collect();
+        assert!(matches!(registry.resolve_report(&ReportID(1), &items), Ok(None)));
+    }
+
+    #[test]
+    fn resolve_report_reports_expected_and_actual_arity_on_mismatch() {
+        let mut registry = ReportRegistry::new();
+        let report_id = ReportID(1);
+        registry.define(report_id.clone(), ReportDefinition { variables: vec![VariableID(10), VariableID(11)] });
+
+        let items: VecList<Item> = std::iter::empty().collect();
+        let err = registry.resolve_report(&report_id, &items).unwrap_err();
+        assert_eq!(err.report_id, report_id);
+        assert_eq!(err.expected, 2);
+        assert_eq!(err.actual, 0);
+    }
+}
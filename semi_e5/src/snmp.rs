@@ -0,0 +1,174 @@
+// Copyright © 2024 Nathaniel Hardesty
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to
+// deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+// IN THE SOFTWARE.
+
+//! # SNMP TRAP EXPORT
+//!
+//! ---------------------------------------------------------------------------
+//!
+//! Bridges [AlarmReportSend] (S5F1) and [EventReport] (S6F11) messages
+//! northbound as SNMPv2c/v3 notification (trap) PDUs, so an SNMP manager
+//! can be notified of equipment alarms and collection events without a
+//! host polling for them over SECS-II.
+//!
+//! Gated behind the `snmp` feature so the core SECS-II crate stays
+//! dependency-light; with the feature disabled this module does not exist.
+//!
+//! ---------------------------------------------------------------------------
+//!
+//! [AlarmReportSend]: crate::messages::s5::AlarmReportSend
+//! [EventReport]: crate::messages::s6::EventReport
+
+use std::collections::HashMap;
+
+use crate::items::*;
+use crate::messages::s5::AlarmReportSend;
+use crate::messages::s6::EventReport;
+
+/// SNMP manager endpoint and the enterprise OID new traps are rooted under.
+#[derive(Debug, Clone)]
+pub struct SnmpConfig {
+    pub manager_addr: std::net::SocketAddr,
+    pub enterprise_oid: Vec<u32>,
+    /// Per-[AlarmID] OID suffix, appended to `enterprise_oid` so a manager
+    /// can route or filter traps by alarm. Alarms with no entry fall back
+    /// to a single `0` suffix.
+    pub alarm_oid_suffixes: HashMap<AlarmID, Vec<u32>>,
+    pub auth: SnmpAuth,
+}
+
+/// Credentials for the configured SNMP manager.
+#[derive(Debug, Clone)]
+pub enum SnmpAuth {
+    /// SNMPv2c community string.
+    V2c { community: String },
+    /// SNMPv3 USM credentials.
+    V3 { engine_id: Vec<u8>, user: String },
+}
+
+/// A single notification PDU produced by [SnmpBridge], ready for a
+/// [SnmpTransport] to encode and send.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrapPdu {
+    pub oid: Vec<u32>,
+    pub varbinds: Vec<(Vec<u32>, String)>,
+}
+
+/// Pluggable delivery for [TrapPdu]s, so users can supply their own socket,
+/// async runtime, or test harness instead of one chosen by this crate.
+pub trait SnmpTransport {
+    type Error;
+
+    /// Encodes and sends `pdu` to the manager described by `config`.
+    fn send(&mut self, config: &SnmpConfig, pdu: TrapPdu) -> Result<(), Self::Error>;
+}
+
+/// Converts [AlarmReportSend] and [EventReport] messages into [TrapPdu]s
+/// rooted under a configured enterprise OID.
+#[derive(Debug, Clone)]
+pub struct SnmpBridge {
+    config: SnmpConfig,
+}
+
+impl SnmpBridge {
+    pub fn new(config: SnmpConfig) -> Self {
+        Self { config }
+    }
+
+    /// Builds the alarm-raised or alarm-cleared trap for an incoming
+    /// [AlarmReportSend], keyed off the set/clear bit of its [AlarmCode].
+    ///
+    /// [AlarmID] maps to its configured OID suffix (see
+    /// [SnmpConfig::alarm_oid_suffixes]) and [AlarmText] to a varbind
+    /// string.
+    pub fn alarm_trap(&self, report: &AlarmReportSend) -> TrapPdu {
+        let (code, id, text) = &report.0;
+        TrapPdu {
+            oid: self.alarm_oid(id, code.is_set()),
+            varbinds: vec![
+                (self.varbind_oid(&[1, 1]), format!("{:?}", id)),
+                (self.varbind_oid(&[1, 2]), format!("{:?}", code.category())),
+                (self.varbind_oid(&[1, 3]), text.to_string()),
+            ],
+        }
+    }
+
+    /// The trap OID for an alarm: the configured per-alarm suffix (or a
+    /// single `0` if none is registered) followed by the set/clear
+    /// variant.
+    fn alarm_oid(&self, id: &AlarmID, is_set: bool) -> Vec<u32> {
+        let suffix = self.config.alarm_oid_suffixes.get(id).map(Vec::as_slice).unwrap_or(&[0]);
+        let mut oid = self.config.enterprise_oid.clone();
+        oid.extend_from_slice(suffix);
+        oid.push(if is_set { 1 } else { 2 });
+        oid
+    }
+
+    /// Builds a generic event-notification trap for an incoming
+    /// [EventReport], carrying the CEID and the flattened report variable
+    /// list.
+    pub fn event_trap(&self, report: &EventReport) -> TrapPdu {
+        let (_, ceid, reports) = &report.0;
+        let mut varbinds = vec![(self.varbind_oid(&[2, 1]), format!("{:?}", ceid))];
+        for (report_id, items) in reports.iter() {
+            for item in items.iter() {
+                varbinds.push((self.varbind_oid(&[2, 2]), format!("{:?}: {:?}", report_id, item)));
+            }
+        }
+        TrapPdu { oid: self.varbind_oid(&[2, 0]), varbinds }
+    }
+
+    fn varbind_oid(&self, suffix: &[u32]) -> Vec<u32> {
+        self.config.enterprise_oid.iter().copied().chain(suffix.iter().copied()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(alarm_oid_suffixes: HashMap<AlarmID, Vec<u32>>) -> SnmpConfig {
+        SnmpConfig {
+            manager_addr: "127.0.0.1:162".parse().unwrap(),
+            enterprise_oid: vec![1, 3, 6, 1, 4, 1, 99999],
+            alarm_oid_suffixes,
+            auth: SnmpAuth::V2c { community: "public".to_string() },
+        }
+    }
+
+    #[test]
+    fn alarm_trap_uses_configured_oid_suffix_and_set_clear_variant() {
+        let mut suffixes = HashMap::new();
+        suffixes.insert(AlarmID(5), vec![9, 9]);
+        let bridge = SnmpBridge::new(config(suffixes));
+
+        let report = AlarmReportSend((AlarmCode(0x81), AlarmID(5), AlarmText("high temperature".to_string())));
+        assert_eq!(bridge.alarm_trap(&report).oid, vec![1, 3, 6, 1, 4, 1, 99999, 9, 9, 1]);
+
+        let report = AlarmReportSend((AlarmCode(0x01), AlarmID(5), AlarmText("high temperature".to_string())));
+        assert_eq!(bridge.alarm_trap(&report).oid, vec![1, 3, 6, 1, 4, 1, 99999, 9, 9, 2]);
+    }
+
+    #[test]
+    fn alarm_trap_falls_back_to_a_default_suffix_when_unmapped() {
+        let bridge = SnmpBridge::new(config(HashMap::new()));
+        let report = AlarmReportSend((AlarmCode(0x81), AlarmID(7), AlarmText("unmapped".to_string())));
+        assert_eq!(bridge.alarm_trap(&report).oid, vec![1, 3, 6, 1, 4, 1, 99999, 0, 1]);
+    }
+}